@@ -0,0 +1,251 @@
+//! Content-defined chunking on top of the content-addressable [`HashOdb`].
+//!
+//! A `WeakStore` assumes values are reasonable to keep on the heap, but a
+//! backup tool must also store multi-gigabyte files. This module splits a
+//! large input into chunks whose boundaries depend only on their content (a
+//! rolling gear-hash fingerprint, cutting whenever its low bits are zero),
+//! stores each chunk as its own object, and records their order in a
+//! "chunk list" object. Because boundaries are content-derived, unchanged
+//! regions of an edited file re-chunk to identical oids, so the `HashOdb`'s
+//! own `weak_exists` check deduplicates them automatically.
+
+use std::fmt;
+use std::io::{self, BufReader, Read};
+
+use crate::kv::{Key, StoreError, WeakStore};
+use crate::odb::{HashOdb, Hasher, Oid};
+
+/// Tunable bounds on the chunk sizes produced by [`Chunker`].
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkerConfig {
+    pub min_size: usize,
+    pub avg_size: usize,
+    pub max_size: usize,
+}
+
+impl Default for ChunkerConfig {
+    fn default() -> Self {
+        ChunkerConfig {
+            min_size: 4 * 1024,
+            avg_size: 64 * 1024,
+            max_size: 256 * 1024,
+        }
+    }
+}
+
+impl ChunkerConfig {
+    /// A boundary is cut whenever the low bits of the rolling hash are all
+    /// zero; the number of bits is chosen so that, on average, a boundary
+    /// occurs every `avg_size` bytes.
+    fn boundary_mask(&self) -> u64 {
+        let bits = (self.avg_size.max(1) as f64).log2().round() as u32;
+        (1u64 << bits) - 1
+    }
+}
+
+const fn gear_table() -> [u64; 256] {
+    // A fixed pseudo-random table (a splitmix64 expansion of the byte
+    // index), used to turn each input byte into a wide, well-mixed
+    // contribution to the rolling hash.
+    let mut table = [0u64; 256];
+    let mut seed: u64 = 0;
+    let mut i = 0;
+    while i < 256 {
+        seed = seed.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = seed;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^= z >> 31;
+        table[i] = z;
+        i += 1;
+    }
+    table
+}
+
+static GEAR: [u64; 256] = gear_table();
+
+/// Splits a byte stream into content-defined chunks using a gear-hash
+/// rolling fingerprint.
+///
+/// `reader` is wrapped in a [`BufReader`] so that scanning the rolling hash
+/// byte-by-byte -- necessary, since a boundary can fall anywhere -- costs
+/// one read syscall per internal buffer fill rather than one per byte.
+pub struct Chunker<R> {
+    reader: BufReader<R>,
+    config: ChunkerConfig,
+}
+
+impl<R: Read> Chunker<R> {
+    pub fn new(reader: R, config: ChunkerConfig) -> Self {
+        Chunker {
+            reader: BufReader::new(reader),
+            config,
+        }
+    }
+
+    /// Read the next chunk, or `None` at end of stream.
+    pub fn next_chunk(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let mask = self.config.boundary_mask();
+        let mut chunk = Vec::new();
+        let mut hash: u64 = 0;
+        let mut byte = [0u8; 1];
+
+        loop {
+            if self.reader.read(&mut byte)? == 0 {
+                break;
+            }
+
+            chunk.push(byte[0]);
+            hash = (hash << 1).wrapping_add(GEAR[byte[0] as usize]);
+
+            if chunk.len() >= self.config.max_size {
+                break;
+            }
+            if chunk.len() >= self.config.min_size && hash & mask == 0 {
+                break;
+            }
+        }
+
+        if chunk.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(chunk))
+        }
+    }
+}
+
+/// The stored content did not decode as a well-formed chunk list.
+#[derive(Debug)]
+pub struct InvalidChunkListError;
+
+impl fmt::Display for InvalidChunkListError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "chunk list object was not valid UTF-8")
+    }
+}
+
+impl std::error::Error for InvalidChunkListError {}
+
+/// A chunk recorded in a chunk list was not found while reassembling it.
+#[derive(Debug)]
+pub struct MissingChunkError(Oid);
+
+impl fmt::Display for MissingChunkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "chunk {} is missing from the store", self.0.as_key().as_str())
+    }
+}
+
+impl std::error::Error for MissingChunkError {}
+
+/// An ordered list of child chunk oids, itself stored as a regular object.
+///
+/// Encoded as one hex oid per line; reassembly streams the chunks back in
+/// this recorded order.
+struct ChunkList(Vec<Oid>);
+
+impl ChunkList {
+    fn encode(&self) -> Vec<u8> {
+        let mut buf = String::new();
+        for oid in &self.0 {
+            buf.push_str(oid.as_key().as_str());
+            buf.push('\n');
+        }
+        buf.into_bytes()
+    }
+
+    fn decode(bytes: &[u8]) -> Result<ChunkList, StoreError> {
+        let s = std::str::from_utf8(bytes).map_err(|_| StoreError::corruption(InvalidChunkListError))?;
+
+        let mut oids = Vec::new();
+        for line in s.lines() {
+            if line.is_empty() {
+                continue;
+            }
+            oids.push(Oid::from_key(Key::new(line)?));
+        }
+
+        Ok(ChunkList(oids))
+    }
+}
+
+/// Splits `reader` into content-defined chunks (per `config`), stores each
+/// as its own object via `odb`, and stores a chunk-list object recording
+/// their order. Returns the oid of that chunk-list object.
+pub fn put_chunked<S: WeakStore, H: Hasher, R: Read>(
+    odb: &mut HashOdb<S, H>,
+    reader: R,
+    config: ChunkerConfig,
+) -> Result<Oid, StoreError> {
+    let mut chunker = Chunker::new(reader, config);
+    let mut children = Vec::new();
+
+    while let Some(chunk) = chunker.next_chunk().map_err(StoreError::backend)? {
+        children.push(odb.put_object(&chunk)?);
+    }
+
+    odb.put_object(&ChunkList(children).encode())
+}
+
+/// Reassembles the object addressed by `oid` (as produced by
+/// [`put_chunked`]), returning a [`Read`] that streams its chunks back in
+/// order, fetching each one from `odb` lazily as it is consumed.
+///
+/// Returns `Ok(None)` if the chunk-list object itself is not (yet) present --
+/// consistent with the underlying `WeakStore`'s eventual-consistency rules.
+/// A chunk that goes missing partway through the stream (the same
+/// eventual-consistency case, discovered later) surfaces as an `io::Error`
+/// from the returned reader rather than as a `StoreError`, since `Read`
+/// has no room for that distinction.
+pub fn get_chunked<'o, S: WeakStore, H: Hasher>(
+    odb: &'o mut HashOdb<S, H>,
+    oid: &Oid,
+) -> Result<Option<ChunkedReader<'o, S, H>>, StoreError> {
+    let list_bytes = match odb.get_object(oid)? {
+        Some(bytes) => bytes,
+        None => return Ok(None),
+    };
+
+    let list = ChunkList::decode(&list_bytes)?;
+
+    Ok(Some(ChunkedReader {
+        odb,
+        children: list.0.into_iter(),
+        current: None,
+    }))
+}
+
+/// Streams the reassembled content of a [`put_chunked`] object. See
+/// [`get_chunked`].
+pub struct ChunkedReader<'o, S: WeakStore, H: Hasher> {
+    odb: &'o mut HashOdb<S, H>,
+    children: std::vec::IntoIter<Oid>,
+    current: Option<(Vec<u8>, usize)>,
+}
+
+impl<'o, S: WeakStore, H: Hasher> Read for ChunkedReader<'o, S, H> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if let Some((data, pos)) = &mut self.current {
+                if *pos < data.len() {
+                    let n = buf.len().min(data.len() - *pos);
+                    buf[..n].copy_from_slice(&data[*pos..*pos + n]);
+                    *pos += n;
+                    return Ok(n);
+                }
+                self.current = None;
+            }
+
+            let child = match self.children.next() {
+                Some(child) => child,
+                None => return Ok(0),
+            };
+
+            match self.odb.get_object(&child) {
+                Ok(Some(data)) => self.current = Some((data, 0)),
+                Ok(None) => return Err(io::Error::new(io::ErrorKind::NotFound, MissingChunkError(child))),
+                Err(e) => return Err(io::Error::other(e)),
+            }
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use crate::kv::StoreError;
+
+/// Raw operations against an opaque binary object store (S3/GCS-compatible).
+///
+/// Implementations are expected to be eventually consistent in the same
+/// sense [`WeakStore`](crate::kv::WeakStore) is: a `set()` that returns
+/// successfully is not guaranteed to be immediately visible to a subsequent
+/// `get()` or `list()`, from any observer including the one that wrote it.
+/// [`super::impl_objectstore::ObjectWeakStore`] is what adapts a `BlobStore`
+/// into a `WeakStore` that copes with this.
+///
+/// A merely transient failure (a throttled request, a dropped connection)
+/// should be reported as `StoreError::Unavailable` rather than
+/// `StoreError::Backend`, so that `ObjectWeakStore`'s retry loop (which
+/// checks [`StoreError::is_retryable`](crate::kv::StoreError::is_retryable))
+/// knows to keep polling instead of failing the caller outright.
+pub trait BlobStore {
+    /// Fetch the object at `path`, or `None` if it does not exist (or is not
+    /// yet visible).
+    fn get(&mut self, path: &str) -> Result<Option<Vec<u8>>, StoreError>;
+
+    /// Durably write `value` to `path`, overwriting any existing object.
+    fn set(&mut self, path: &str, value: &[u8]) -> Result<(), StoreError>;
+
+    /// Remove the object at `path`, if any.
+    fn delete(&mut self, path: &str) -> Result<(), StoreError>;
+
+    /// List the paths of all objects whose path starts with `prefix`.
+    fn list(&mut self, prefix: &str) -> Result<Vec<String>, StoreError>;
+}
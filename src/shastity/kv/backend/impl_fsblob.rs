@@ -0,0 +1,91 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::blob::BlobStore;
+use super::StoreOptions;
+use crate::kv::StoreError;
+
+/// A [`BlobStore`] backed by ordinary files in a directory, one file per
+/// object path.
+///
+/// This is a real, usable backend (suitable for local development and
+/// tests), not just a stand-in -- a genuine object-storage deployment would
+/// instead back [`ObjectWeakStore`](super::impl_objectstore::ObjectWeakStore)
+/// with a client for whatever service is in use (S3, GCS, ...), implementing
+/// this same trait.
+pub struct FsBlobStore {
+    root: PathBuf,
+}
+
+impl FsBlobStore {
+    /// Open (and, unless `options` says otherwise, create) the directory
+    /// rooted at `root` that objects are stored under.
+    ///
+    /// Only `create_if_missing` is honoured: `read_only` is ignored, and
+    /// `set`/`delete` write to the directory regardless.
+    pub fn open<P: AsRef<Path>>(root: P, options: &StoreOptions) -> Result<FsBlobStore, StoreError> {
+        if options.is_create_if_missing() {
+            fs::create_dir_all(root.as_ref()).map_err(StoreError::backend)?;
+        }
+
+        Ok(FsBlobStore { root: root.as_ref().to_owned() })
+    }
+
+    fn path_for(&self, path: &str) -> PathBuf {
+        self.root.join(path)
+    }
+}
+
+impl BlobStore for FsBlobStore {
+    fn get(&mut self, path: &str) -> Result<Option<Vec<u8>>, StoreError> {
+        match fs::read(self.path_for(path)) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(StoreError::backend(e)),
+        }
+    }
+
+    fn set(&mut self, path: &str, value: &[u8]) -> Result<(), StoreError> {
+        // Write to a temporary file, fsync it, and rename it into place so a
+        // reader never observes a partially written object (the atomicity
+        // WeakStore::weak_put relies on) and the write is durable once set()
+        // returns.
+        let dest = self.path_for(path);
+        let tmp = self.root.join(format!(".{}.tmp-{}", path, std::process::id()));
+
+        let mut file = fs::File::create(&tmp).map_err(StoreError::backend)?;
+        io::Write::write_all(&mut file, value).map_err(StoreError::backend)?;
+        file.sync_all().map_err(StoreError::backend)?;
+        drop(file);
+        fs::rename(&tmp, &dest).map_err(StoreError::backend)
+    }
+
+    fn delete(&mut self, path: &str) -> Result<(), StoreError> {
+        match fs::remove_file(self.path_for(path)) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(StoreError::backend(e)),
+        }
+    }
+
+    fn list(&mut self, prefix: &str) -> Result<Vec<String>, StoreError> {
+        let entries = match fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(StoreError::backend(e)),
+        };
+
+        let mut paths = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(StoreError::backend)?;
+            if let Some(name) = entry.file_name().to_str() {
+                if name.starts_with(prefix) && !name.starts_with('.') {
+                    paths.push(name.to_owned());
+                }
+            }
+        }
+
+        Ok(paths)
+    }
+}
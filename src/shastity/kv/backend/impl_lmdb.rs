@@ -0,0 +1,216 @@
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use lmdb::{Database, DatabaseFlags, Environment as LmdbEnvironment};
+use lmdb::{Cursor as LmdbCursorExt, EnvironmentFlags, Transaction, WriteFlags};
+
+use super::StoreOptions;
+use crate::kv::{Cursor, Key, Store, StoreError, WeakStore};
+
+type LmdbEntry<'c> = Result<(&'c [u8], &'c [u8]), lmdb::Error>;
+
+/// A write was attempted against a store opened with `StoreOptions::read_only(true)`.
+#[derive(Debug)]
+struct ReadOnlyError;
+
+impl fmt::Display for ReadOnlyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "store was opened read-only")
+    }
+}
+
+impl std::error::Error for ReadOnlyError {}
+
+/// A single on-disk LMDB environment, following the same split rkv uses
+/// between an environment and the (possibly many) named stores opened
+/// within it.
+pub struct Environment {
+    env: Arc<LmdbEnvironment>,
+}
+
+impl Environment {
+    /// Open (and, unless `options` says otherwise, create) the LMDB
+    /// environment rooted at `path`.
+    pub fn open<P: AsRef<Path>>(path: P, options: &StoreOptions) -> Result<Environment, StoreError> {
+        if options.is_create_if_missing() {
+            std::fs::create_dir_all(path.as_ref()).map_err(StoreError::backend)?;
+        }
+
+        let mut builder = LmdbEnvironment::new();
+        builder.set_max_dbs(1024);
+        if options.is_read_only() {
+            builder.set_flags(EnvironmentFlags::READ_ONLY);
+        }
+
+        let env = builder.open(path.as_ref()).map_err(StoreError::backend)?;
+
+        Ok(Environment { env: Arc::new(env) })
+    }
+
+    /// Open the named store within this environment, creating it if
+    /// `options` allows.
+    pub fn open_store(&self, name: &str, options: &StoreOptions) -> Result<LmdbStore, StoreError> {
+        let db = if options.is_create_if_missing() {
+            self.env.create_db(Some(name), DatabaseFlags::empty())
+        } else {
+            self.env.open_db(Some(name))
+        }
+        .map_err(StoreError::backend)?;
+
+        Ok(LmdbStore {
+            env: self.env.clone(),
+            db,
+            read_only: options.is_read_only(),
+        })
+    }
+}
+
+/// A single named store within an LMDB [`Environment`], implementing both
+/// [`WeakStore`] and [`Store`].
+pub struct LmdbStore {
+    env: Arc<LmdbEnvironment>,
+    db: Database,
+    read_only: bool,
+}
+
+impl WeakStore for LmdbStore {
+    fn weak_get(&mut self, key: &Key) -> Result<Option<Vec<u8>>, StoreError> {
+        let txn = self.env.begin_ro_txn().map_err(StoreError::backend)?;
+        match txn.get(self.db, &key.as_str()) {
+            Ok(bytes) => Ok(Some(bytes.to_vec())),
+            Err(lmdb::Error::NotFound) => Ok(None),
+            Err(e) => Err(StoreError::backend(e)),
+        }
+    }
+
+    fn weak_put(&mut self, key: &Key, value: &[u8]) -> Result<(), StoreError> {
+        if self.read_only {
+            return Err(StoreError::backend(ReadOnlyError));
+        }
+
+        let mut txn = self.env.begin_rw_txn().map_err(StoreError::backend)?;
+        txn.put(self.db, &key.as_str(), &value, WriteFlags::empty())
+            .map_err(StoreError::backend)?;
+        txn.commit().map_err(StoreError::backend)?;
+
+        // weak_put()'s durability contract requires the value be durably
+        // stored before returning; LMDB only guarantees that once the
+        // environment has been synced to disk.
+        self.env.sync(true).map_err(StoreError::backend)
+    }
+
+    fn weak_exists(&mut self, key: &Key) -> Result<bool, StoreError> {
+        Ok(self.weak_get(key)?.is_some())
+    }
+
+    fn weak_delete(&mut self, key: &Key) -> Result<(), StoreError> {
+        let mut txn = self.env.begin_rw_txn().map_err(StoreError::backend)?;
+        match txn.del(self.db, &key.as_str(), None) {
+            Ok(()) => (),
+            Err(lmdb::Error::NotFound) => (),
+            Err(e) => return Err(StoreError::backend(e)),
+        }
+        txn.commit().map_err(StoreError::backend)
+    }
+
+    /// Implemented with a read-only LMDB cursor positioned via `iter_from`,
+    /// which (like all LMDB iteration) visits keys in their sorted order --
+    /// exactly the ordering a `Cursor` needs to be resumable.
+    fn weak_iter_from(
+        &mut self,
+        cursor: Option<Cursor>,
+        limit: usize,
+    ) -> Result<(Vec<Key>, Option<Cursor>), StoreError> {
+        if limit == 0 {
+            // An empty page here must not be mistaken for exhaustion, so
+            // hand the cursor straight back rather than advancing past it.
+            return Ok((Vec::new(), cursor));
+        }
+
+        let txn = self.env.begin_ro_txn().map_err(StoreError::backend)?;
+        let mut db_cursor = txn.open_ro_cursor(self.db).map_err(StoreError::backend)?;
+
+        let mut page = Vec::new();
+        let mut more = false;
+
+        let items: Box<dyn Iterator<Item = LmdbEntry>> = match &cursor {
+            None => Box::new(db_cursor.iter_start()),
+            Some(c) => {
+                let after = c.as_bytes();
+                let mut it = db_cursor.iter_from(after).peekable();
+                if let Some(Ok((k, _))) = it.peek() {
+                    if *k == after {
+                        it.next();
+                    }
+                }
+                Box::new(it)
+            }
+        };
+
+        for item in items {
+            let (k, _v) = item.map_err(StoreError::backend)?;
+            if page.len() >= limit {
+                more = true;
+                break;
+            }
+            let key_str = std::str::from_utf8(k).map_err(StoreError::backend)?;
+            page.push(Key::new(key_str).expect("stored key was valid"));
+        }
+
+        let next_cursor = if more {
+            page.last().map(Cursor::after_key)
+        } else {
+            None
+        };
+
+        Ok((page, next_cursor))
+    }
+}
+
+impl Store for LmdbStore {
+    fn get(&mut self, key: &Key) -> Result<Option<Vec<u8>>, StoreError> {
+        WeakStore::weak_get(self, key)
+    }
+
+    fn put(&mut self, key: &Key, value: &[u8]) -> Result<(), StoreError> {
+        WeakStore::weak_put(self, key, value)
+    }
+
+    /// Implemented as a single LMDB write transaction: the current value is
+    /// read and compared against `expected_value`, and the new value is only
+    /// written (and the transaction only committed) if they match. Because
+    /// LMDB serializes writers against a single write transaction at a time,
+    /// this check-then-act sequence is genuinely atomic with respect to any
+    /// other writer.
+    fn put_if(
+        &mut self,
+        key: &[u8],
+        expected_value: Option<&[u8]>,
+        new_value: &[u8],
+    ) -> Result<(), StoreError> {
+        if self.read_only {
+            return Err(StoreError::backend(ReadOnlyError));
+        }
+
+        let mut txn = self.env.begin_rw_txn().map_err(StoreError::backend)?;
+        let current = match txn.get(self.db, &key) {
+            Ok(bytes) => Some(bytes),
+            Err(lmdb::Error::NotFound) => None,
+            Err(e) => return Err(StoreError::backend(e)),
+        };
+
+        if current != expected_value {
+            return Err(StoreError::CasMismatch);
+        }
+
+        txn.put(self.db, &key, &new_value, WriteFlags::empty())
+            .map_err(StoreError::backend)?;
+        txn.commit().map_err(StoreError::backend)?;
+        self.env.sync(true).map_err(StoreError::backend)
+    }
+
+    fn exists(&mut self, key: &Key) -> Result<bool, StoreError> {
+        WeakStore::weak_exists(self, key)
+    }
+}
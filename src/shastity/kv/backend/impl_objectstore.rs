@@ -0,0 +1,176 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::blob::BlobStore;
+use crate::kv::{Cursor, Key, StoreError, WeakStore};
+
+/// Exponential backoff configuration used while polling an eventually
+/// consistent [`BlobStore`] for visibility of a freshly-written object.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    deadline: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(5),
+            deadline: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn initial_backoff(mut self, d: Duration) -> Self {
+        self.initial_backoff = d;
+        self
+    }
+
+    pub fn max_backoff(mut self, d: Duration) -> Self {
+        self.max_backoff = d;
+        self
+    }
+
+    /// How long to keep polling before giving up: the object is then reported
+    /// as absent, or the last retryable error is returned if the final
+    /// attempt failed with one.
+    pub fn deadline(mut self, d: Duration) -> Self {
+        self.deadline = d;
+        self
+    }
+
+    /// Repeatedly call `op` until it returns `Some`, or until `deadline`
+    /// elapses (in which case `None` is returned), backing off
+    /// exponentially between attempts.
+    ///
+    /// An `Err` is only propagated immediately if it is not
+    /// [retryable](StoreError::is_retryable) -- a transient failure (for
+    /// example `StoreError::Unavailable`) is treated the same as a not-yet-visible
+    /// object and retried until `deadline`, at which point it is finally
+    /// propagated.
+    fn retry<T>(
+        &self,
+        mut op: impl FnMut() -> Result<Option<T>, StoreError>,
+    ) -> Result<Option<T>, StoreError> {
+        let start = Instant::now();
+        let mut backoff = self.initial_backoff;
+
+        loop {
+            let last_err = match op() {
+                Ok(Some(value)) => return Ok(Some(value)),
+                Ok(None) => None,
+                Err(e) if e.is_retryable() => Some(e),
+                Err(e) => return Err(e),
+            };
+
+            if start.elapsed() >= self.deadline {
+                return match last_err {
+                    Some(e) => Err(e),
+                    None => Ok(None),
+                };
+            }
+
+            thread::sleep(backoff.min(self.max_backoff));
+            backoff = (backoff * 2).min(self.max_backoff);
+        }
+    }
+}
+
+/// Adapts any [`BlobStore`] (S3, GCS, ...) into a [`WeakStore`] by mapping
+/// each [`Key`] onto an object path equal to the key's own hex string.
+///
+/// Because blob stores are only eventually consistent, `weak_get` and
+/// `weak_exists` poll the backend with exponential backoff (per
+/// [`RetryPolicy`]) until the object becomes visible or the deadline
+/// elapses. `weak_put` stays a single, durable write -- the trait only
+/// requires that a put be durable once acknowledged, not that it be
+/// immediately visible, so there is nothing to retry there.
+pub struct ObjectWeakStore<B: BlobStore> {
+    blobs: B,
+    retry: RetryPolicy,
+}
+
+impl<B: BlobStore> ObjectWeakStore<B> {
+    pub fn new(blobs: B) -> Self {
+        ObjectWeakStore {
+            blobs,
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    pub fn with_retry_policy(blobs: B, retry: RetryPolicy) -> Self {
+        ObjectWeakStore { blobs, retry }
+    }
+
+    fn path_for(key: &Key) -> String {
+        key.as_str().to_owned()
+    }
+}
+
+impl<B: BlobStore> WeakStore for ObjectWeakStore<B> {
+    fn weak_get(&mut self, key: &Key) -> Result<Option<Vec<u8>>, StoreError> {
+        let path = Self::path_for(key);
+        self.retry.retry(|| self.blobs.get(&path))
+    }
+
+    fn weak_put(&mut self, key: &Key, value: &[u8]) -> Result<(), StoreError> {
+        self.blobs.set(&Self::path_for(key), value)
+    }
+
+    /// Polls with the same [`RetryPolicy`] as `weak_get`: a negative
+    /// existence check is indistinguishable from "not yet visible", so a key
+    /// that is genuinely absent costs the full `RetryPolicy::deadline` before
+    /// `false` is returned.
+    fn weak_exists(&mut self, key: &Key) -> Result<bool, StoreError> {
+        Ok(self.weak_get(key)?.is_some())
+    }
+
+    fn weak_delete(&mut self, key: &Key) -> Result<(), StoreError> {
+        self.blobs.delete(&Self::path_for(key))
+    }
+
+    fn weak_iter_from(
+        &mut self,
+        cursor: Option<Cursor>,
+        limit: usize,
+    ) -> Result<(Vec<Key>, Option<Cursor>), StoreError> {
+        if limit == 0 {
+            // An empty page here must not be mistaken for exhaustion, so
+            // hand the cursor straight back rather than advancing past it.
+            return Ok((Vec::new(), cursor));
+        }
+
+        let mut paths = self.blobs.list("")?;
+        paths.sort();
+
+        let start = match &cursor {
+            None => 0,
+            Some(c) => {
+                let after = std::str::from_utf8(c.as_bytes()).map_err(StoreError::backend)?;
+                paths.partition_point(|p| p.as_str() <= after)
+            }
+        };
+
+        let page: Vec<Key> = paths
+            .iter()
+            .skip(start)
+            .take(limit)
+            .map(|p| Key::new(p.as_str()).expect("stored path was a valid key"))
+            .collect();
+
+        let next_cursor = if start + page.len() < paths.len() {
+            page.last().map(Cursor::after_key)
+        } else {
+            None
+        };
+
+        Ok((page, next_cursor))
+    }
+}
@@ -0,0 +1,55 @@
+pub mod blob;
+pub mod impl_fsblob;
+pub mod impl_lmdb;
+pub mod impl_objectstore;
+
+/// Configuration shared by all backends for opening an on-disk environment
+/// or store.
+///
+/// The `WeakStore`/`Store` docs are explicit that callers, not the trait,
+/// are responsible for picking an appropriate implementation and
+/// configuration. `StoreOptions` is how that choice is expressed to a
+/// backend: whether to create the on-disk environment if it doesn't yet
+/// exist, and whether to open it read-only.
+#[derive(Debug, Clone, Copy)]
+pub struct StoreOptions {
+    create_if_missing: bool,
+    read_only: bool,
+}
+
+impl Default for StoreOptions {
+    fn default() -> Self {
+        StoreOptions {
+            create_if_missing: true,
+            read_only: false,
+        }
+    }
+}
+
+impl StoreOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// If set, the environment (and any store opened within it) is created
+    /// if it does not already exist. Defaults to true.
+    pub fn create_if_missing(mut self, yes: bool) -> Self {
+        self.create_if_missing = yes;
+        self
+    }
+
+    /// If set, open the environment without acquiring write access.
+    /// Defaults to false.
+    pub fn read_only(mut self, yes: bool) -> Self {
+        self.read_only = yes;
+        self
+    }
+
+    pub fn is_create_if_missing(&self) -> bool {
+        self.create_if_missing
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+}
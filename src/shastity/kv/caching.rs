@@ -0,0 +1,149 @@
+use std::collections::{HashMap, VecDeque};
+use std::rc::{Rc, Weak};
+
+use super::{Cursor, Key, StoreError, WeakStore};
+
+/// The number of most-recently-used entries [`CachingWeakStore`] keeps alive
+/// via a strong reference of its own, in addition to whatever the caller may
+/// still be holding via [`get_shared`](CachingWeakStore::get_shared).
+const DEFAULT_MRU_CAPACITY: usize = 128;
+
+/// Wraps any [`WeakStore`] with an in-process cache of recently-used values,
+/// modeled on a weak-value map: each cached blob is only reachable through a
+/// [`Weak`] reference, so an entry is evicted once nothing is still holding
+/// it alive. To make `weak_get`/`weak_put` themselves useful as a cache --
+/// not just [`get_shared`](CachingWeakStore::get_shared) -- the store also
+/// keeps its own bounded most-recently-used list of strong [`Rc`]s, evicting
+/// the least-recently-used one once the list is full. This keeps cache
+/// memory bounded by the configured capacity plus actual in-flight usage,
+/// rather than collapsing to nothing the moment a caller's own `Rc` goes out
+/// of scope.
+///
+/// `weak_get` checks the cache (upgrading the weak pointer) before falling
+/// through to the backend, and repopulates the cache on miss. `weak_exists`
+/// checks the cache the same way but, on a miss, only asks the backend's
+/// `weak_exists` and leaves the cache unpopulated.
+/// `weak_put` writes through to the backend and seeds the cache.
+/// `weak_delete` invalidates the cached entry.
+///
+/// The MRU list is keyed, not just a list of `Rc`s: repeatedly touching the
+/// same key moves its one entry to the front rather than appending a
+/// duplicate, so the bounded capacity is spent on distinct keys. The weak
+/// table itself is pruned of dead entries whenever a new value is inserted,
+/// so it doesn't grow without bound as values fall out of use.
+///
+/// This is safe under `WeakStore`'s eventual-consistency rules as long as
+/// the cache is never asked to serve a value for a key that was deleted
+/// through some path other than this wrapper's own `weak_delete`.
+pub struct CachingWeakStore<S: WeakStore> {
+    backend: S,
+    cache: HashMap<String, Weak<Vec<u8>>>,
+    mru: VecDeque<(String, Rc<Vec<u8>>)>,
+    mru_capacity: usize,
+}
+
+impl<S: WeakStore> CachingWeakStore<S> {
+    pub fn new(backend: S) -> Self {
+        Self::with_mru_capacity(backend, DEFAULT_MRU_CAPACITY)
+    }
+
+    /// Like [`new`](CachingWeakStore::new), but with an explicit bound on
+    /// how many entries the cache keeps alive by itself.
+    pub fn with_mru_capacity(backend: S, mru_capacity: usize) -> Self {
+        CachingWeakStore {
+            backend,
+            cache: HashMap::new(),
+            mru: VecDeque::new(),
+            mru_capacity,
+        }
+    }
+
+    /// Records `strong` as the most-recently-used entry for `key`, evicting
+    /// the least-recently-used one if the list is now over capacity.
+    ///
+    /// Any existing MRU entry for `key` is removed first, so repeatedly
+    /// touching the same key can't fill the bounded list with duplicates of
+    /// itself at the expense of other keys' capacity.
+    fn touch_mru(&mut self, key: &Key, strong: Rc<Vec<u8>>) {
+        self.mru.retain(|(k, _)| k != key.as_str());
+        self.mru.push_front((key.as_str().to_owned(), strong));
+        while self.mru.len() > self.mru_capacity {
+            self.mru.pop_back();
+        }
+    }
+
+    /// Drops cache entries whose `Weak` has already lost its last strong
+    /// reference, so the table doesn't grow without bound as values fall out
+    /// of use.
+    fn prune_dead(&mut self) {
+        self.cache.retain(|_, weak| weak.strong_count() > 0);
+    }
+
+    /// Inserts `value` into the cache under `key`, both as the weak entry
+    /// looked up by future `weak_get`/`weak_exists` calls and as a strong
+    /// reference in the MRU list.
+    fn insert(&mut self, key: &Key, value: Vec<u8>) -> Rc<Vec<u8>> {
+        let strong = Rc::new(value);
+        self.cache
+            .insert(key.as_str().to_owned(), Rc::downgrade(&strong));
+        self.touch_mru(key, strong.clone());
+        self.prune_dead();
+        strong
+    }
+
+    /// Like `weak_get`, but returns a reference-counted handle rather than a
+    /// fresh copy. Holding onto the returned `Rc` keeps the entry alive in
+    /// the cache beyond the bounded MRU list this store keeps on its own.
+    pub fn get_shared(&mut self, key: &Key) -> Result<Option<Rc<Vec<u8>>>, StoreError> {
+        if let Some(weak) = self.cache.get(key.as_str()) {
+            if let Some(strong) = weak.upgrade() {
+                self.touch_mru(key, strong.clone());
+                return Ok(Some(strong));
+            }
+        }
+
+        match self.backend.weak_get(key)? {
+            Some(value) => Ok(Some(self.insert(key, value))),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<S: WeakStore> WeakStore for CachingWeakStore<S> {
+    fn weak_get(&mut self, key: &Key) -> Result<Option<Vec<u8>>, StoreError> {
+        Ok(self.get_shared(key)?.map(|rc| (*rc).clone()))
+    }
+
+    fn weak_put(&mut self, key: &Key, value: &[u8]) -> Result<(), StoreError> {
+        self.backend.weak_put(key, value)?;
+        self.insert(key, value.to_owned());
+        Ok(())
+    }
+
+    fn weak_exists(&mut self, key: &Key) -> Result<bool, StoreError> {
+        if let Some(weak) = self.cache.get(key.as_str()) {
+            if weak.upgrade().is_some() {
+                return Ok(true);
+            }
+        }
+        self.backend.weak_exists(key)
+    }
+
+    fn weak_delete(&mut self, key: &Key) -> Result<(), StoreError> {
+        self.cache.remove(key.as_str());
+        self.mru.retain(|(k, _)| k != key.as_str());
+        self.backend.weak_delete(key)
+    }
+
+    fn weak_iter(&mut self) -> Box<dyn Iterator<Item = Result<Key, StoreError>>> {
+        self.backend.weak_iter()
+    }
+
+    fn weak_iter_from(
+        &mut self,
+        cursor: Option<Cursor>,
+        limit: usize,
+    ) -> Result<(Vec<Key>, Option<Cursor>), StoreError> {
+        self.backend.weak_iter_from(cursor, limit)
+    }
+}
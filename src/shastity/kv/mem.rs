@@ -33,7 +33,41 @@ impl super::WeakStore for MemWeakStore {
         Ok(())
     }
 
-    fn weak_iter(&mut self) -> Box<dyn Iterator<Item = Result<super::Key, super::StoreError>>> {
-        unimplemented!()
+    fn weak_iter_from(
+        &mut self,
+        cursor: Option<super::Cursor>,
+        limit: usize,
+    ) -> Result<(Vec<super::Key>, Option<super::Cursor>), super::StoreError> {
+        if limit == 0 {
+            // An empty page here must not be mistaken for exhaustion, so
+            // hand the cursor straight back rather than advancing past it.
+            return Ok((Vec::new(), cursor));
+        }
+
+        let mut keys: Vec<&String> = self.map.keys().collect();
+        keys.sort();
+
+        let start = match &cursor {
+            None => 0,
+            Some(c) => {
+                let after = std::str::from_utf8(c.as_bytes()).map_err(super::StoreError::backend)?;
+                keys.partition_point(|k| k.as_str() <= after)
+            }
+        };
+
+        let page: Vec<super::Key> = keys
+            .iter()
+            .skip(start)
+            .take(limit)
+            .map(|k| super::Key::new(k.as_str()).expect("stored key was valid"))
+            .collect();
+
+        let next_cursor = if start + page.len() < keys.len() {
+            page.last().map(super::Cursor::after_key)
+        } else {
+            None
+        };
+
+        Ok((page, next_cursor))
     }
 }
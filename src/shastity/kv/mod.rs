@@ -1,21 +1,104 @@
+pub mod backend;
+pub mod caching;
 pub mod mem;
 
 use std::error::Error;
 use std::fmt;
 use std::option::Option;
 
+/// The error type returned by `kv` operations.
+///
+/// Backends are expected to classify their failures into one of these
+/// variants rather than inventing their own error types, so that callers
+/// (in particular the backoff logic in eventually-consistent backends, see
+/// [`is_retryable`](StoreError::is_retryable)) have one consistent surface
+/// to reason about regardless of which backend they're talking to.
 #[derive(Debug)]
-pub struct StoreError {}
+#[non_exhaustive]
+pub enum StoreError {
+    /// No value exists for a key an operation required to be present.
+    NotFound,
+
+    /// The key as given was not valid.
+    InvalidKey(InvalidKeyError),
+
+    /// A `Store::put_if` was rejected because the current value did not
+    /// match the caller's expectation.
+    CasMismatch,
+
+    /// The backend failed in a way specific to its own implementation.
+    Backend(Box<dyn Error + Send + Sync>),
+
+    /// Stored content failed to validate against an expectation (for
+    /// example, a content-addressed object no longer matching its address).
+    Corruption(Box<dyn Error + Send + Sync>),
+
+    /// The backend is temporarily unavailable; the operation may succeed if
+    /// retried later.
+    Unavailable(Box<dyn Error + Send + Sync>),
+}
+
+impl StoreError {
+    pub fn backend<E: Error + Send + Sync + 'static>(cause: E) -> StoreError {
+        StoreError::Backend(Box::new(cause))
+    }
+
+    pub fn corruption<E: Error + Send + Sync + 'static>(cause: E) -> StoreError {
+        StoreError::Corruption(Box::new(cause))
+    }
+
+    pub fn unavailable<E: Error + Send + Sync + 'static>(cause: E) -> StoreError {
+        StoreError::Unavailable(Box::new(cause))
+    }
+
+    /// Whether the operation that produced this error might succeed if
+    /// simply retried, as opposed to a permanent failure like corruption or
+    /// an invalid key.
+    ///
+    /// ```
+    /// # use shastity::kv::StoreError;
+    /// # use std::io;
+    /// assert!(StoreError::unavailable(io::Error::new(io::ErrorKind::Other, "retry me")).is_retryable());
+    /// assert!(!StoreError::backend(io::Error::new(io::ErrorKind::Other, "permanent")).is_retryable());
+    /// assert!(!StoreError::corruption(io::Error::new(io::ErrorKind::Other, "corrupt")).is_retryable());
+    /// assert!(!StoreError::NotFound.is_retryable());
+    /// assert!(!StoreError::CasMismatch.is_retryable());
+    /// ```
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, StoreError::Unavailable(_))
+    }
+}
 
 impl Error for StoreError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
-        unimplemented!()
+        match self {
+            StoreError::NotFound | StoreError::CasMismatch => None,
+            StoreError::InvalidKey(e) => Some(e),
+            StoreError::Backend(e) | StoreError::Corruption(e) | StoreError::Unavailable(e) => {
+                Some(e.as_ref())
+            }
+        }
     }
 }
 
 impl fmt::Display for StoreError {
-    fn fmt(&self, _f: &mut fmt::Formatter) -> fmt::Result {
-        unimplemented!()
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StoreError::NotFound => write!(f, "key not found"),
+            StoreError::InvalidKey(e) => write!(f, "invalid key: {}", e),
+            StoreError::CasMismatch => {
+                write!(f, "compare-and-set failed: current value did not match expectation")
+            }
+            StoreError::Backend(e) => write!(f, "backend error: {}", e),
+            StoreError::Corruption(e) => write!(f, "stored content is corrupt: {}", e),
+            StoreError::Unavailable(e) => write!(f, "store temporarily unavailable: {}", e),
+        }
+    }
+}
+
+impl From<InvalidKeyError> for StoreError {
+    fn from(e: InvalidKeyError) -> Self {
+        StoreError::InvalidKey(e)
     }
 }
 
@@ -30,6 +113,19 @@ pub enum InvalidKeyError {
     Empty,
 }
 
+impl fmt::Display for InvalidKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InvalidKeyError::InvalidCharacter => {
+                write!(f, "key contained a character outside [0-9a-f]")
+            }
+            InvalidKeyError::Empty => write!(f, "key was empty"),
+        }
+    }
+}
+
+impl Error for InvalidKeyError {}
+
 /// A key with which values can be assocaited in a store.
 ///
 /// A key is a string guaranteed to be non-empty and contain only `[0-9a-f]`.
@@ -41,7 +137,7 @@ pub enum InvalidKeyError {
 /// let key = Key::new("abcd").unwrap();
 /// let s = String::from(key);
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Key {
     s: String,
 }
@@ -58,11 +154,11 @@ impl Key {
     /// assert!(Key::new("").is_err());
     /// assert!(Key::new("BBCDEF").is_err());
     /// ```
-    pub fn new<T: Into<String>>(k: T) -> Result<Self, InvalidKeyError> {
+    pub fn new<T: Into<String>>(k: T) -> Result<Self, StoreError> {
         let s = k.into();
 
         if s.is_empty() {
-            return Err(InvalidKeyError::Empty);
+            return Err(InvalidKeyError::Empty.into());
         }
 
         for c in s.chars() {
@@ -71,7 +167,7 @@ impl Key {
                 'a'..='f' => true,
                 _ => false,
             } {
-                return Err(InvalidKeyError::InvalidCharacter);
+                return Err(InvalidKeyError::InvalidCharacter.into());
             }
         }
 
@@ -109,8 +205,25 @@ impl From<&Key> for String {
     }
 }
 
+/// An opaque continuation token produced by [`WeakStore::weak_iter_from`],
+/// encoding the position a subsequent call should resume iteration from.
+///
+/// The only valid way to obtain a `Cursor` is from a prior call to
+/// `weak_iter_from`; callers should treat its contents as opaque.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Cursor(Vec<u8>);
 
+impl Cursor {
+    /// A cursor positioned just after `key`.
+    fn after_key(key: &Key) -> Cursor {
+        Cursor(key.as_str().as_bytes().to_vec())
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
 /// Provides storage of key->value mappings of reasonable size with weakened semantics sufficient for
 /// use by a content addressable store, but maximally relaxed to allow implementation
 /// flexibility and efficiency.
@@ -207,8 +320,64 @@ pub trait WeakStore {
     /// Iteration is only successful if the iterator is finished *and* if all results
     /// consumed were Ok().
     ///
-    /// TODO: This interface does not allow for resumption nor concurrency, it should.
-    fn weak_iter(&mut self) -> Box<dyn Iterator<Item = Result<Key, StoreError>>>;
+    /// This interface does not allow for resumption nor concurrency; see
+    /// [`weak_iter_from`](WeakStore::weak_iter_from) for a variant that
+    /// allows resumption (but not sharding a scan across workers).
+    ///
+    /// The default implementation simply pages through
+    /// [`weak_iter_from`](WeakStore::weak_iter_from) until it is exhausted,
+    /// collecting every key before returning -- the `Box<dyn Iterator>`
+    /// returned here carries no lifetime tying it back to `&mut self`, so
+    /// unlike `weak_iter_from` it cannot stream, and implementations backed
+    /// by very large stores should prefer calling `weak_iter_from` directly.
+    fn weak_iter(&mut self) -> Box<dyn Iterator<Item = Result<Key, StoreError>>> {
+        let mut keys = Vec::new();
+        let mut cursor = None;
+
+        loop {
+            let (page, next) = match self.weak_iter_from(cursor, 1024) {
+                Ok(paged) => paged,
+                Err(e) => return Box::new(std::iter::once(Err(e))),
+            };
+
+            keys.extend(page);
+
+            match next {
+                Some(c) => cursor = Some(c),
+                None => break,
+            }
+        }
+
+        Box::new(keys.into_iter().map(Ok))
+    }
+
+    /// Returns up to `limit` keys, resuming from `cursor` if given.
+    ///
+    /// # Return value
+    ///
+    /// The returned `Vec<Key>` holds at most `limit` keys. The returned
+    /// `Option<Cursor>` is `Some` if the store may hold further keys beyond
+    /// those returned (pass it back in on the next call to continue), or
+    /// `None` once iteration is exhausted. A `limit` of zero returns no keys
+    /// and hands `cursor` back unchanged, so a `None` returned then does not
+    /// signal exhaustion.
+    ///
+    /// The cursor only encodes a resume position, not a range -- it has no
+    /// notion of an end bound or a prefix restriction. That makes it safe to
+    /// hand a cursor to a single worker that's resuming a previously
+    /// interrupted scan, but it does **not** support sharding a scan across
+    /// independent workers (e.g. partitioning by prefix for a parallel
+    /// garbage-collection pass): there is no way to stop one worker at
+    /// another's starting point, so every worker would run to the end of the
+    /// store.
+    ///
+    /// The same eventual-consistency exceptions to completeness that apply
+    /// to [`weak_iter`](WeakStore::weak_iter) apply here as well.
+    fn weak_iter_from(
+        &mut self,
+        cursor: Option<Cursor>,
+        limit: usize,
+    ) -> Result<(Vec<Key>, Option<Cursor>), StoreError>;
 }
 
 /// Provides storage of key->value mappings of reasonable size with strongly consistent semantics.
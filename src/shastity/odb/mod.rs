@@ -1,56 +1,162 @@
-use std::error::Error;
 use std::fmt;
-use std::vec::Vec;
 
-pub struct Oid(String);
-pub struct Content(Vec<u8>);
+use crate::kv::{Key, StoreError, WeakStore};
 
+/// The content-derived identifier of an object stored in a [`HashOdb`].
+///
+/// Because an `Oid` can only be produced by hashing content (via
+/// [`HashOdb::identify_object`]), two objects with the same id are
+/// guaranteed to be identical, and can be compared for equality without
+/// pulling their content out of the store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Oid(Key);
+
+impl Oid {
+    pub(crate) fn from_key(key: Key) -> Oid {
+        Oid(key)
+    }
+
+    pub fn as_key(&self) -> &Key {
+        &self.0
+    }
+}
+
+impl From<Oid> for Key {
+    fn from(oid: Oid) -> Key {
+        oid.0
+    }
+}
+
+/// A pluggable cryptographic hash function used by [`HashOdb`] to derive
+/// object ids from content.
+///
+/// The digest is hex-encoded to produce the `Oid`, so implementations don't
+/// need to worry about `Key`'s `[0-9a-f]` alphabet restriction -- a hex
+/// encoding always satisfies it.
+pub trait Hasher {
+    fn hash(&self, content: &[u8]) -> Vec<u8>;
+}
+
+/// [`Hasher`] backed by BLAKE3.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Blake3Hasher;
+
+impl Hasher for Blake3Hasher {
+    fn hash(&self, content: &[u8]) -> Vec<u8> {
+        blake3::hash(content).as_bytes().to_vec()
+    }
+}
+
+/// [`Hasher`] backed by SHA-256.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Sha256Hasher;
+
+impl Hasher for Sha256Hasher {
+    fn hash(&self, content: &[u8]) -> Vec<u8> {
+        use sha2::{Digest, Sha256};
+        Sha256::digest(content).to_vec()
+    }
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        s.push_str(&format!("{:02x}", b));
+    }
+    s
+}
+
+/// The content stored at an `Oid` no longer hashes to that `Oid`.
 #[derive(Debug)]
-pub struct OdbError {
-    cause: Option<Box<dyn Error>>,
+pub struct CorruptObjectError {
+    expected: Oid,
+    actual: Oid,
+}
+
+impl fmt::Display for CorruptObjectError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "object stored at {} no longer matches its address (now hashes to {})",
+            self.expected.as_key().as_str(),
+            self.actual.as_key().as_str()
+        )
+    }
 }
 
-/// A content addressable object database.
+impl std::error::Error for CorruptObjectError {}
+
+/// A content addressable object database backed by any [`WeakStore`].
 ///
-/// For information on the general concept of content addressable
-/// storage, see:
+/// For information on the general concept of content addressable storage,
+/// see:
 ///
 ///   https://en.wikipedia.org/wiki/Content-addressable_storage
 ///
-/// Properties of an Odb include:
+/// Properties of a `HashOdb` include:
 ///
 ///   - If two objects have the same id, they are identical. Thus,
 ///     two objects can be compared for equality without pulling the
 ///     content out of the store.
 ///   - Once an object is recorded in the store, it does not go away
-///     unless explicit removal if requested through some means beyond
-///     the scope of this trait. In other words, puts are durable.
-///     For example, a local file system store would probably need to
-///     fsync() prior to returning. (Exact semantics are up to implementation
-///     and user configuration.)
-///   - Callers cannot construct oids other than by giving the store the contents
-///     to associate with the oid.
-pub trait Odb {
-    fn identify_object(content: &Content) -> Result<Oid, OdbError>;
-    fn put_object(content: &Content) -> Result<Oid, OdbError>;
-    fn get_object(oid: &Oid) -> Result<Content, OdbError>;
+///     unless explicit removal is requested through some means beyond
+///     the scope of this type -- durability is inherited from the
+///     underlying `WeakStore`.
+///   - Callers cannot construct oids other than by giving the store the
+///     content to associate with the oid.
+pub struct HashOdb<S: WeakStore, H: Hasher> {
+    store: S,
+    hasher: H,
 }
 
-impl Error for OdbError {
-    fn source(&self) -> Option<&(dyn Error + 'static)> {
-        self.cause.as_deref()
+impl<S: WeakStore, H: Hasher> HashOdb<S, H> {
+    pub fn new(store: S, hasher: H) -> Self {
+        HashOdb { store, hasher }
     }
-}
 
-impl fmt::Display for OdbError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "OdbError")?;
-        match self.source() {
-            None => Ok(()),
-            Some(source) => {
-                f.write_str(" caused by: ")?;
-                fmt::Display::fmt(source, f)
-            }
+    /// Compute the `Oid` that `content` would be stored under, without
+    /// storing it.
+    pub fn identify_object(&self, content: &[u8]) -> Result<Oid, StoreError> {
+        let digest = self.hasher.hash(content);
+        let key = Key::new(hex_encode(&digest))?;
+        Ok(Oid::from_key(key))
+    }
+
+    /// Store `content`, returning its `Oid`. A no-op if an object with the
+    /// same id is already present, giving content-addressed dedup for free.
+    pub fn put_object(&mut self, content: &[u8]) -> Result<Oid, StoreError> {
+        let oid = self.identify_object(content)?;
+
+        if !self.store.weak_exists(oid.as_key())? {
+            self.store.weak_put(oid.as_key(), content)?;
+        }
+
+        Ok(oid)
+    }
+
+    /// Fetch the object addressed by `oid`.
+    ///
+    /// # Return value
+    ///
+    /// `Ok(None)` indicates the object does not exist, or has not yet
+    /// become readable (see `WeakStore`'s eventual-consistency rules).
+    ///
+    /// The content is re-hashed on read; if it no longer matches `oid`, a
+    /// `StoreError::Corruption` is returned.
+    pub fn get_object(&mut self, oid: &Oid) -> Result<Option<Vec<u8>>, StoreError> {
+        let content = match self.store.weak_get(oid.as_key())? {
+            Some(content) => content,
+            None => return Ok(None),
+        };
+
+        let actual = self.identify_object(&content)?;
+        if &actual != oid {
+            return Err(StoreError::corruption(CorruptObjectError {
+                expected: oid.clone(),
+                actual,
+            }));
         }
+
+        Ok(Some(content))
     }
 }
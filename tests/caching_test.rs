@@ -0,0 +1,113 @@
+extern crate shastity;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use shastity::kv::caching::CachingWeakStore;
+use shastity::kv::mem::MemWeakStore;
+use shastity::kv::{Cursor, Key, StoreError, WeakStore};
+
+fn k(s: &str) -> Key {
+    Key::new(s).unwrap()
+}
+
+/// A `WeakStore` wrapping `MemWeakStore` that counts how many times
+/// `weak_get` actually reaches it, so tests can assert a `CachingWeakStore`
+/// served a request entirely from cache.
+struct CountingWeakStore {
+    inner: MemWeakStore,
+    gets: Rc<Cell<usize>>,
+}
+
+impl WeakStore for CountingWeakStore {
+    fn weak_get(&mut self, key: &Key) -> Result<Option<Vec<u8>>, StoreError> {
+        self.gets.set(self.gets.get() + 1);
+        self.inner.weak_get(key)
+    }
+
+    fn weak_put(&mut self, key: &Key, value: &[u8]) -> Result<(), StoreError> {
+        self.inner.weak_put(key, value)
+    }
+
+    fn weak_exists(&mut self, key: &Key) -> Result<bool, StoreError> {
+        self.inner.weak_exists(key)
+    }
+
+    fn weak_delete(&mut self, key: &Key) -> Result<(), StoreError> {
+        self.inner.weak_delete(key)
+    }
+
+    fn weak_iter(&mut self) -> Box<dyn Iterator<Item = Result<Key, StoreError>>> {
+        self.inner.weak_iter()
+    }
+
+    fn weak_iter_from(
+        &mut self,
+        cursor: Option<Cursor>,
+        limit: usize,
+    ) -> Result<(Vec<Key>, Option<Cursor>), StoreError> {
+        self.inner.weak_iter_from(cursor, limit)
+    }
+}
+
+#[test]
+fn weak_get_after_weak_put_is_served_from_cache() {
+    let gets = Rc::new(Cell::new(0));
+    let backend = CountingWeakStore {
+        inner: MemWeakStore::new(),
+        gets: gets.clone(),
+    };
+    let mut store = CachingWeakStore::new(backend);
+
+    store.weak_put(&k("abc"), b"v").expect("weak_put should have succeeded");
+    assert_eq!(gets.get(), 0, "weak_put should not itself call weak_get on the backend");
+
+    let value = store.weak_get(&k("abc")).expect("weak_get should have succeeded");
+    assert_eq!(value.as_deref(), Some(b"v".as_slice()));
+    assert_eq!(gets.get(), 0, "weak_put should have seeded the cache, so weak_get should not touch the backend");
+}
+
+#[test]
+fn touching_one_key_repeatedly_does_not_evict_other_keys_from_the_mru() {
+    // With an MRU capacity of 2 and only 2 distinct keys ever touched,
+    // neither key should ever lose its strong MRU backing, no matter how
+    // many times one of them is re-touched -- a buggy touch_mru that pushes
+    // a duplicate entry per touch instead of deduping by key would eventually
+    // evict the other key purely from repeated touches of this one.
+    let gets = Rc::new(Cell::new(0));
+    let backend = CountingWeakStore {
+        inner: MemWeakStore::new(),
+        gets: gets.clone(),
+    };
+    let mut store = CachingWeakStore::with_mru_capacity(backend, 2);
+
+    store.weak_put(&k("1"), b"v1").expect("weak_put should have succeeded");
+    store.weak_put(&k("2"), b"v2").expect("weak_put should have succeeded");
+
+    for _ in 0..5 {
+        store.weak_get(&k("1")).expect("weak_get should have succeeded");
+    }
+    assert_eq!(gets.get(), 0, "key 1 should never have missed through to the backend");
+
+    store.weak_get(&k("2")).expect("weak_get should have succeeded");
+    assert_eq!(gets.get(), 0, "key 2 should still have been served from cache");
+}
+
+#[test]
+fn repeated_weak_get_is_served_from_cache_after_the_first_miss() {
+    // Seed the backend directly (bypassing weak_put's own cache seeding) so
+    // the first weak_get through the cache is a genuine miss.
+    let gets = Rc::new(Cell::new(0));
+    let mut backend = CountingWeakStore {
+        inner: MemWeakStore::new(),
+        gets: gets.clone(),
+    };
+    backend.inner.weak_put(&k("abc"), b"v").expect("weak_put should have succeeded");
+    let mut store = CachingWeakStore::new(backend);
+
+    store.weak_get(&k("abc")).expect("weak_get should have succeeded");
+    assert_eq!(gets.get(), 1, "the first weak_get should have missed through to the backend");
+
+    store.weak_get(&k("abc")).expect("weak_get should have succeeded");
+    assert_eq!(gets.get(), 1, "the second weak_get should have been served from cache");
+}
@@ -0,0 +1,52 @@
+extern crate shastity;
+
+use std::io::Read;
+
+use shastity::chunk::{get_chunked, put_chunked, ChunkerConfig};
+use shastity::kv::mem::MemWeakStore;
+use shastity::odb::{Blake3Hasher, HashOdb};
+
+fn config() -> ChunkerConfig {
+    ChunkerConfig {
+        min_size: 16,
+        avg_size: 64,
+        max_size: 128,
+    }
+}
+
+#[test]
+fn roundtrip_across_many_chunk_boundaries() {
+    let mut odb = HashOdb::new(MemWeakStore::new(), Blake3Hasher);
+
+    let content: Vec<u8> = (0..10_000u32).map(|i| (i % 256) as u8).collect();
+
+    let oid = put_chunked(&mut odb, content.as_slice(), config()).expect("put_chunked should have succeeded");
+
+    let mut reader = get_chunked(&mut odb, &oid)
+        .expect("get_chunked should have succeeded")
+        .expect("chunk list should have been present");
+
+    let mut roundtripped = Vec::new();
+    reader.read_to_end(&mut roundtripped).expect("reading the chunked object should have succeeded");
+
+    assert_eq!(roundtripped, content);
+}
+
+#[test]
+fn rechunking_identical_content_dedupes_to_the_same_oid() {
+    let mut odb = HashOdb::new(MemWeakStore::new(), Blake3Hasher);
+    let content = vec![7u8; 5_000];
+
+    let first = put_chunked(&mut odb, content.as_slice(), config()).expect("put_chunked should have succeeded");
+    let second = put_chunked(&mut odb, content.as_slice(), config()).expect("put_chunked should have succeeded");
+
+    assert_eq!(first, second);
+}
+
+#[test]
+fn get_chunked_on_unknown_oid_returns_none() {
+    let mut odb = HashOdb::new(MemWeakStore::new(), Blake3Hasher);
+    let bogus = odb.identify_object(b"never stored").expect("identify_object should have succeeded");
+
+    assert!(get_chunked(&mut odb, &bogus).expect("get_chunked should have succeeded").is_none());
+}
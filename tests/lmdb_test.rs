@@ -0,0 +1,130 @@
+extern crate shastity;
+
+use std::fs;
+use std::path::PathBuf;
+
+use shastity::kv::backend::impl_lmdb::Environment;
+use shastity::kv::backend::StoreOptions;
+use shastity::kv::{Key, Store, StoreError, WeakStore};
+
+mod weakstore;
+
+fn k(s: &str) -> Key {
+    Key::new(s).unwrap()
+}
+
+/// A directory under the system temp dir that is removed when it drops, so
+/// a panicking test doesn't leave an LMDB environment behind.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> TempDir {
+        let dir = std::env::temp_dir().join(format!("shastity-lmdb-test-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        TempDir(dir)
+    }
+
+    fn path(&self) -> &std::path::Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn weakstore_contract() {
+    let dir = TempDir::new("weakstore-contract");
+    let env = Environment::open(dir.path(), &StoreOptions::new()).expect("environment should have opened");
+
+    // Each helper expects a fresh store, so give each its own named store
+    // within the one environment rather than sharing state between them.
+    let mut get_store = env.open_store("weak_get", &StoreOptions::new()).expect("store should have opened");
+    weakstore::test_weak_get(&mut get_store);
+
+    let mut put_store = env.open_store("weak_put", &StoreOptions::new()).expect("store should have opened");
+    weakstore::test_weak_put(&mut put_store);
+
+    let mut exists_store = env.open_store("weak_exists", &StoreOptions::new()).expect("store should have opened");
+    weakstore::test_weak_exists(&mut exists_store);
+
+    let mut delete_store = env.open_store("weak_delete", &StoreOptions::new()).expect("store should have opened");
+    weakstore::test_weak_delete(&mut delete_store);
+
+    let mut iter_store = env.open_store("weak_iter_from", &StoreOptions::new()).expect("store should have opened");
+    weakstore::test_weak_iter_from(&mut iter_store);
+}
+
+#[test]
+fn put_if_is_atomic_with_respect_to_the_expected_value() {
+    let dir = TempDir::new("put-if-cas");
+    let env = Environment::open(dir.path(), &StoreOptions::new()).expect("environment should have opened");
+    let mut store = env.open_store("store", &StoreOptions::new()).expect("store should have opened");
+
+    // Absent -> present only succeeds if the caller correctly expected None.
+    match store.put_if(b"abc", Some(b"wrong guess"), b"v1") {
+        Err(StoreError::CasMismatch) => (),
+        other => panic!("expected CasMismatch against an absent key, got {:?}", other),
+    }
+    store
+        .put_if(b"abc", None, b"v1")
+        .expect("put_if should have succeeded against an absent key with expected_value None");
+
+    // Present -> present only succeeds if the caller's expectation matches
+    // the current value exactly.
+    match store.put_if(b"abc", Some(b"not v1"), b"v2") {
+        Err(StoreError::CasMismatch) => (),
+        other => panic!("expected CasMismatch against a mismatched current value, got {:?}", other),
+    }
+    assert_eq!(store.get(&k("abc")).unwrap(), Some(b"v1".to_vec()), "the mismatched put_if must not have written anything");
+
+    store
+        .put_if(b"abc", Some(b"v1"), b"v2")
+        .expect("put_if should have succeeded once the expectation matched");
+    assert_eq!(store.get(&k("abc")).unwrap(), Some(b"v2".to_vec()));
+}
+
+#[test]
+fn weak_put_is_durable_across_a_fresh_environment_handle() {
+    let dir = TempDir::new("durability");
+
+    {
+        let env = Environment::open(dir.path(), &StoreOptions::new()).expect("environment should have opened");
+        let mut store = env.open_store("store", &StoreOptions::new()).expect("store should have opened");
+        store.weak_put(&k("abc"), b"v").expect("weak_put should have succeeded");
+        // `env` (and the mmap it holds) is dropped here, simulating a
+        // process restart between the write and the read below.
+    }
+
+    let env = Environment::open(dir.path(), &StoreOptions::new()).expect("environment should have reopened");
+    let mut store = env.open_store("store", &StoreOptions::new()).expect("store should have reopened");
+    assert_eq!(
+        store.weak_get(&k("abc")).expect("weak_get should have succeeded"),
+        Some(b"v".to_vec()),
+        "a weak_put acknowledged before the environment was dropped must survive a fresh handle"
+    );
+}
+
+#[test]
+fn a_read_only_environment_rejects_writes() {
+    let dir = TempDir::new("read-only");
+
+    {
+        let env = Environment::open(dir.path(), &StoreOptions::new()).expect("environment should have opened");
+        let mut store = env.open_store("store", &StoreOptions::new()).expect("store should have opened");
+        store.weak_put(&k("abc"), b"v").expect("weak_put should have succeeded");
+    }
+
+    let options = StoreOptions::new().read_only(true).create_if_missing(false);
+    let env = Environment::open(dir.path(), &options).expect("environment should have reopened read-only");
+    let mut store = env.open_store("store", &options).expect("store should have reopened read-only");
+
+    assert_eq!(store.weak_get(&k("abc")).expect("weak_get should have succeeded"), Some(b"v".to_vec()));
+    match store.weak_put(&k("def"), b"v") {
+        Err(StoreError::Backend(_)) => (),
+        other => panic!("expected a read-only store to reject writes, got {:?}", other),
+    }
+}
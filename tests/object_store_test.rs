@@ -0,0 +1,144 @@
+extern crate shastity;
+
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::io;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use shastity::kv::backend::blob::BlobStore;
+use shastity::kv::backend::impl_objectstore::{ObjectWeakStore, RetryPolicy};
+use shastity::kv::{Key, StoreError, WeakStore};
+
+fn k(s: &str) -> Key {
+    Key::new(s).unwrap()
+}
+
+fn unavailable() -> StoreError {
+    StoreError::unavailable(io::Error::new(io::ErrorKind::Other, "temporarily unavailable"))
+}
+
+fn permanent() -> StoreError {
+    StoreError::backend(io::Error::new(io::ErrorKind::Other, "permanent failure"))
+}
+
+/// A [`BlobStore`] whose `get` plays back a fixed script of responses, one
+/// per call, falling back to `Ok(None)` once the script runs out; used to
+/// drive `RetryPolicy::retry` through specific sequences deterministically.
+struct ScriptedBlobStore {
+    responses: VecDeque<Result<Option<Vec<u8>>, StoreError>>,
+    calls: Rc<Cell<usize>>,
+}
+
+impl BlobStore for ScriptedBlobStore {
+    fn get(&mut self, _path: &str) -> Result<Option<Vec<u8>>, StoreError> {
+        self.calls.set(self.calls.get() + 1);
+        self.responses.pop_front().unwrap_or(Ok(None))
+    }
+
+    fn set(&mut self, _path: &str, _value: &[u8]) -> Result<(), StoreError> {
+        Ok(())
+    }
+
+    fn delete(&mut self, _path: &str) -> Result<(), StoreError> {
+        Ok(())
+    }
+
+    fn list(&mut self, _prefix: &str) -> Result<Vec<String>, StoreError> {
+        Ok(Vec::new())
+    }
+}
+
+fn fast_retry_policy() -> RetryPolicy {
+    RetryPolicy::new()
+        .initial_backoff(Duration::from_millis(1))
+        .max_backoff(Duration::from_millis(2))
+        .deadline(Duration::from_millis(200))
+}
+
+#[test]
+fn polls_until_the_object_becomes_visible() {
+    let calls = Rc::new(Cell::new(0));
+    let blobs = ScriptedBlobStore {
+        responses: VecDeque::from([Ok(None), Ok(None), Ok(Some(b"v".to_vec()))]),
+        calls: calls.clone(),
+    };
+    let mut store = ObjectWeakStore::with_retry_policy(blobs, fast_retry_policy());
+
+    let value = store.weak_get(&k("abc")).expect("weak_get should have succeeded");
+    assert_eq!(value, Some(b"v".to_vec()));
+    assert_eq!(calls.get(), 3, "should have polled twice before the third attempt succeeded");
+}
+
+#[test]
+fn retries_a_retryable_error_until_success() {
+    let calls = Rc::new(Cell::new(0));
+    let blobs = ScriptedBlobStore {
+        responses: VecDeque::from([Err(unavailable()), Err(unavailable()), Ok(Some(b"v".to_vec()))]),
+        calls: calls.clone(),
+    };
+    let mut store = ObjectWeakStore::with_retry_policy(blobs, fast_retry_policy());
+
+    let value = store.weak_get(&k("abc")).expect("weak_get should have succeeded after retrying");
+    assert_eq!(value, Some(b"v".to_vec()));
+    assert_eq!(calls.get(), 3);
+}
+
+#[test]
+fn a_non_retryable_error_propagates_without_retrying() {
+    let calls = Rc::new(Cell::new(0));
+    let blobs = ScriptedBlobStore {
+        responses: VecDeque::from([Err(permanent())]),
+        calls: calls.clone(),
+    };
+    // A long deadline: if this were (wrongly) retried, the test would hang
+    // until the deadline instead of returning immediately.
+    let policy = fast_retry_policy().deadline(Duration::from_secs(30));
+    let mut store = ObjectWeakStore::with_retry_policy(blobs, policy);
+
+    match store.weak_get(&k("abc")) {
+        Err(StoreError::Backend(_)) => (),
+        other => panic!("expected an immediate Backend error, got {:?}", other),
+    }
+    assert_eq!(calls.get(), 1, "a non-retryable error must not be retried");
+}
+
+#[test]
+fn deadline_expiry_reports_absence_when_never_visible() {
+    let calls = Rc::new(Cell::new(0));
+    let blobs = ScriptedBlobStore {
+        responses: VecDeque::new(),
+        calls: calls.clone(),
+    };
+    let policy = RetryPolicy::new()
+        .initial_backoff(Duration::from_millis(2))
+        .max_backoff(Duration::from_millis(4))
+        .deadline(Duration::from_millis(30));
+    let mut store = ObjectWeakStore::with_retry_policy(blobs, policy);
+
+    let start = Instant::now();
+    let value = store.weak_get(&k("abc")).expect("weak_get should have succeeded");
+    assert_eq!(value, None);
+    assert!(start.elapsed() >= Duration::from_millis(30));
+    assert!(calls.get() > 1, "should have polled more than once before giving up");
+}
+
+#[test]
+fn deadline_expiry_propagates_the_last_retryable_error() {
+    let calls = Rc::new(Cell::new(0));
+    let blobs = ScriptedBlobStore {
+        responses: std::iter::repeat_with(|| Err(unavailable())).take(100).collect(),
+        calls: calls.clone(),
+    };
+    let policy = RetryPolicy::new()
+        .initial_backoff(Duration::from_millis(2))
+        .max_backoff(Duration::from_millis(4))
+        .deadline(Duration::from_millis(30));
+    let mut store = ObjectWeakStore::with_retry_policy(blobs, policy);
+
+    match store.weak_get(&k("abc")) {
+        Err(StoreError::Unavailable(_)) => (),
+        other => panic!("expected the last Unavailable error once the deadline expired, got {:?}", other),
+    }
+    assert!(calls.get() > 1);
+}
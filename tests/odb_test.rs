@@ -0,0 +1,66 @@
+extern crate shastity;
+
+use shastity::kv::mem::MemWeakStore;
+use shastity::kv::{Key, WeakStore};
+use shastity::odb::{Blake3Hasher, HashOdb};
+
+#[test]
+fn put_object_dedupes_identical_content() {
+    let mut odb = HashOdb::new(MemWeakStore::new(), Blake3Hasher);
+
+    let first = odb.put_object(b"hello").expect("put_object should have succeeded");
+    let second = odb.put_object(b"hello").expect("put_object should have succeeded");
+
+    assert_eq!(first, second);
+}
+
+#[test]
+fn get_object_roundtrips_stored_content() {
+    let mut odb = HashOdb::new(MemWeakStore::new(), Blake3Hasher);
+
+    let oid = odb.put_object(b"hello").expect("put_object should have succeeded");
+    let content = odb
+        .get_object(&oid)
+        .expect("get_object should have succeeded")
+        .expect("object should have been present");
+
+    assert_eq!(content, b"hello");
+}
+
+#[test]
+fn get_object_on_unknown_oid_returns_none() {
+    let mut odb = HashOdb::new(MemWeakStore::new(), Blake3Hasher);
+    let bogus = odb.identify_object(b"never stored").expect("identify_object should have succeeded");
+
+    assert!(odb.get_object(&bogus).expect("get_object should have succeeded").is_none());
+}
+
+#[test]
+fn get_object_detects_corruption() {
+    // Compute the oid "hello" would be stored under, then plant different
+    // content at that key directly -- simulating corruption of the
+    // underlying store -- bypassing put_object entirely.
+    let probe = HashOdb::new(MemWeakStore::new(), Blake3Hasher);
+    let oid = probe.identify_object(b"hello").expect("identify_object should have succeeded");
+
+    let mut store = MemWeakStore::new();
+    store.weak_put(oid.as_key(), b"tampered").expect("weak_put should have succeeded");
+    let mut odb = HashOdb::new(store, Blake3Hasher);
+
+    match odb.get_object(&oid) {
+        Err(e) => assert!(e.to_string().contains("no longer matches its address")),
+        Ok(_) => panic!("tampered content should have been reported as corrupt"),
+    }
+}
+
+#[test]
+fn identify_object_does_not_store_anything() {
+    let mut odb = HashOdb::new(MemWeakStore::new(), Blake3Hasher);
+    let oid = odb.identify_object(b"hello").expect("identify_object should have succeeded");
+
+    assert!(odb.get_object(&oid).expect("get_object should have succeeded").is_none());
+
+    // Sanity check that the oid's key round-trips through its string form.
+    let key = Key::new(String::from(oid.as_key())).expect("key should round-trip");
+    assert_eq!(key, oid.as_key().clone());
+}
@@ -44,6 +44,43 @@ pub fn test_weak_exists(store: &mut dyn kv::WeakStore) {
     }
 }
 
+/// Exercises resumption of `weak_iter_from`: paging through with a small
+/// limit must yield every key exactly once regardless of how the pages are
+/// split, and a `limit` of zero must not be mistaken for exhaustion.
+pub fn test_weak_iter_from(store: &mut dyn kv::WeakStore) {
+    let inserted = ["abc", "def", "123", "4560", "789a"];
+    for key in &inserted {
+        store.weak_put(&k(key), "v".as_bytes()).expect("weak_put should have succeeded");
+    }
+
+    match store.weak_iter_from(None, 0) {
+        Ok((page, cursor)) => {
+            assert!(page.is_empty(), "a limit of 0 should return no keys");
+            assert!(cursor.is_none(), "a limit of 0 against a fresh cursor should not claim progress");
+        }
+        Err(e) => panic!("weak_iter_from should have succeeded: {}", e),
+    }
+
+    let mut seen: Vec<String> = Vec::new();
+    let mut cursor = None;
+    loop {
+        let (page, next) = store
+            .weak_iter_from(cursor, 2)
+            .expect("weak_iter_from should have succeeded");
+        assert!(page.len() <= 2, "a page must never exceed the requested limit");
+        seen.extend(page.into_iter().map(String::from));
+        match next {
+            Some(c) => cursor = Some(c),
+            None => break,
+        }
+    }
+
+    seen.sort();
+    let mut expected: Vec<String> = inserted.iter().map(|s| s.to_string()).collect();
+    expected.sort();
+    assert_eq!(seen, expected, "paging through weak_iter_from should visit every key exactly once");
+}
+
 pub fn test_weak_delete(store: &mut dyn kv::WeakStore) {
     match store.weak_put(&k("abc"), "v".as_bytes()) {
         Ok(()) => (),
@@ -10,4 +10,5 @@ fn test() {
     weakstore::test_weak_put(&mut mem::MemWeakStore::new());
     weakstore::test_weak_exists(&mut mem::MemWeakStore::new());
     weakstore::test_weak_delete(&mut mem::MemWeakStore::new());
+    weakstore::test_weak_iter_from(&mut mem::MemWeakStore::new());
 }